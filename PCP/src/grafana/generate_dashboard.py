@@ -37,10 +37,10 @@ def load_metrics(csv_path):
 
     with open(csv_path, 'r', newline='') as f:
         reader = csv.reader(f)
-        next(reader)  # Skip header
 
         for row in reader:
-            if row:
+            # Skip the header and the "# pcp_cache_version=N" line of versioned caches
+            if row and row[0] != 'metric_name' and not row[0].startswith('#'):
                 metric_name = row[0]
                 top_level, subcategory = categorize_metric(metric_name)
                 metrics_hierarchy[top_level][subcategory].append(metric_name)
@@ -0,0 +1,111 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use pcp_parser_rust::{parse_pmrep_csv, save_pmrep_csv, synthetic_pmrep_csv, Config};
+use std::env;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Rows in the synthetic pmrep CSV, about a week of 1 second samples
+const ROWS: usize = 500_000;
+
+/// Parse on a single thread and on every available CPU
+fn parse_500k_rows(c: &mut Criterion) {
+    let config = Config::from_env().expect("default configuration");
+    let lines = synthetic_pmrep_csv(ROWS, 50);
+    let cpus = std::thread::available_parallelism().map_or(1, |n| n.get());
+
+    let mut group = c.benchmark_group("parse_500k_rows");
+    group.sample_size(10).throughput(Throughput::Elements(ROWS as u64));
+    let mut thread_counts = vec![1, cpus];
+    thread_counts.dedup();
+    for threads in thread_counts {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .expect("rayon thread pool");
+        group.bench_with_input(BenchmarkId::new("threads", threads), &lines, |b, lines| {
+            b.iter(|| pool.install(|| parse_pmrep_csv(lines, &config).expect("synthetic CSV parses")))
+        });
+    }
+    group.finish();
+}
+
+/// Parse 10k rows with few and with many fields per row, where per-row allocations matter most
+fn parse_10k_rows(c: &mut Criterion) {
+    let config = Config::from_env().expect("default configuration");
+
+    let mut group = c.benchmark_group("parse_10k_rows");
+    group.throughput(Throughput::Elements(10_000));
+    for metrics in [20, 200] {
+        let lines = synthetic_pmrep_csv(10_000, metrics);
+        group.bench_with_input(BenchmarkId::new("metrics", metrics), &lines, |b, lines| {
+            b.iter(|| parse_pmrep_csv(lines, &config).expect("synthetic CSV parses"))
+        });
+    }
+    group.finish();
+}
+
+/// Size of the synthetic pmrep CSV for the buffer size benchmark
+const SAVE_CSV_BYTES: u64 = 1 << 30;
+
+/// Sizes tried for PMREP_READ_BUFFER_KB and PMREP_WRITE_BUFFER_KB, set together
+const BUFFER_KB: [usize; 4] = [8, 64, 256, 1024];
+
+/// Write a synthetic pmrep CSV of at least `bytes` to `dir`, reusing one left by an earlier run
+fn synthetic_csv_file(dir: &Path, bytes: u64) -> PathBuf {
+    let path = dir.join("pmrep_1g.csv");
+    if fs::metadata(&path).is_ok_and(|m| m.len() >= bytes) {
+        return path;
+    }
+
+    let mut file = BufWriter::new(File::create(&path).expect("create synthetic CSV"));
+    let mut written = 0;
+    let mut first = true;
+    while written < bytes {
+        // Timestamps repeat between chunks, which saving the output doesn't look at
+        for line in synthetic_pmrep_csv(100_000, 50).iter().skip(usize::from(!first)) {
+            writeln!(file, "{}", line).expect("write synthetic CSV");
+            written += line.len() as u64 + 1;
+        }
+        first = false;
+    }
+    file.flush().expect("flush synthetic CSV");
+    path
+}
+
+/// Save a 1 GB pmrep CSV, printed by a stand-in pmrep, at each read/write buffer size
+fn save_1gb_pmrep_csv(c: &mut Criterion) {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("save_1gb_pmrep_csv");
+    fs::create_dir_all(dir.join("bin")).expect("create benchmark directory");
+    let csv = synthetic_csv_file(&dir, SAVE_CSV_BYTES);
+
+    let pmrep = dir.join("bin/pmrep");
+    fs::write(&pmrep, format!("#!/bin/sh\nexec cat '{}'\n", csv.display())).expect("write stand-in pmrep");
+    fs::set_permissions(&pmrep, fs::Permissions::from_mode(0o755)).expect("make stand-in pmrep executable");
+    env::set_var("PATH", format!("{}:{}", dir.join("bin").display(), env::var("PATH").unwrap_or_default()));
+    env::set_var("LOG_DIR", &dir);
+
+    let runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build().expect("tokio runtime");
+    let archive = dir.join("bench_archive");
+    let metrics = vec!["bench.metric".to_string()];
+
+    let mut group = c.benchmark_group("save_1gb_pmrep_csv");
+    group
+        .sample_size(10)
+        .measurement_time(Duration::from_secs(60))
+        .throughput(Throughput::Bytes(fs::metadata(&csv).expect("synthetic CSV").len()));
+    for kb in BUFFER_KB {
+        env::set_var("PMREP_READ_BUFFER_KB", kb.to_string());
+        env::set_var("PMREP_WRITE_BUFFER_KB", kb.to_string());
+        let config = Config::from_env().expect("default configuration");
+        group.bench_with_input(BenchmarkId::new("buffer_kb", kb), &config, |b, config| {
+            b.iter(|| runtime.block_on(save_pmrep_csv(&archive, &metrics, config)).expect("pmrep output saves"))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parse_500k_rows, parse_10k_rows, save_1gb_pmrep_csv);
+criterion_main!(benches);